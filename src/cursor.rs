@@ -0,0 +1,71 @@
+//! Tracks the mouse cursor's map position as a resource, folding the
+//! screen -> world -> map conversion every consumer used to hand-roll
+//! (NDC math, the half-width/half-height recenter, the out-of-window
+//! `None` case) into the crate.
+
+use bevy::{prelude::*, window::PrimaryWindow};
+
+/// The width/height [`CursorMapPosPlugin`] recenters world-space positions
+/// against.
+///
+/// Kept separate from [`crate::FovMap`] so this plugin works for any
+/// consumer's own map type, not just the crate's own - insert it alongside
+/// whatever map resource you actually use.
+#[derive(Resource, Clone, Copy)]
+pub struct MapSize {
+    pub width: i32,
+    pub height: i32,
+}
+
+/// The map-space cell under the mouse cursor, or `None` if the cursor is
+/// outside the primary window.
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CursorMapPos(pub Option<IVec2>);
+
+/// Keeps [`CursorMapPos`] up to date from the primary window and camera
+/// each frame. Requires a [`MapSize`] resource to recenter against.
+pub struct CursorMapPosPlugin;
+
+impl Plugin for CursorMapPosPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CursorMapPos>()
+            .add_systems(PreUpdate, update_cursor_map_pos);
+    }
+}
+
+fn update_cursor_map_pos(
+    mut cursor: ResMut<CursorMapPos>,
+    map_size: Res<MapSize>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+) {
+    let pos = windows
+        .get_single()
+        .ok()
+        .and_then(|window| window.cursor_position().map(|p| (window, p)))
+        .and_then(|(_, screen_pos)| cameras.get_single().ok().map(|cam| (cam, screen_pos)))
+        .and_then(|((camera, cam_transform), screen_pos)| {
+            // `viewport_to_world_2d` wants a position relative to the
+            // camera's viewport rect, not the window - for cameras that
+            // set a custom `Viewport` (e.g. bevy_tiled_camera's letterbox/
+            // pillarbox), that rect is offset from the window's origin, so
+            // the raw window cursor position has to be rebased onto it
+            // first or every click lands shifted off the rendered grid.
+            let viewport_pos = match camera.logical_viewport_rect() {
+                Some(rect) => screen_pos - rect.min,
+                None => screen_pos,
+            };
+            camera.viewport_to_world_2d(cam_transform, viewport_pos)
+        })
+        .map(|world_pos| world_to_map(&map_size, world_pos.floor().as_ivec2()));
+
+    if cursor.0 != pos {
+        cursor.0 = pos;
+    }
+}
+
+fn world_to_map(map_size: &MapSize, mut world: IVec2) -> IVec2 {
+    world.x += map_size.width / 2;
+    world.y += map_size.height / 2;
+    world
+}
@@ -0,0 +1,313 @@
+//! Recursive shadowcasting, after Adam Milazzo's
+//! [FOV algorithm](http://www.adammil.net/blog/v125_Roguelike_Vision_Algorithms.html).
+
+use glam::{IVec2, Vec2};
+
+use crate::VisiblityMap;
+
+/// Multipliers that rotate/reflect octant-local `(x, y)` coordinates into
+/// the octant's real position on the map, relative to the origin.
+const MULT: [[i32; 8]; 4] = [
+    [1, 0, 0, -1, -1, 0, 0, 1],
+    [0, 1, -1, 0, 0, -1, 1, 0],
+    [0, 1, 1, 0, 0, -1, -1, 0],
+    [1, 0, 0, 1, -1, 0, 0, -1],
+];
+
+/// A fraction `y / x` compared without floating point error, used to track
+/// the steepest/shallowest slopes bounding the octant's visible wedge.
+#[derive(Clone, Copy)]
+struct Slope {
+    y: i32,
+    x: i32,
+}
+
+impl Slope {
+    fn greater(self, y: i32, x: i32) -> bool {
+        self.y * x > self.x * y
+    }
+
+    fn greater_or_equal(self, y: i32, x: i32) -> bool {
+        self.y * x >= self.x * y
+    }
+
+    fn less(self, y: i32, x: i32) -> bool {
+        self.y * x < self.x * y
+    }
+}
+
+/// Computes field of view from `origin` out to `range`, marking every
+/// visible point on `map` via [`VisiblityMap::set_visible_intensity`], with
+/// intensity falling off linearly from `1.0` at the origin to `0.0` at
+/// `range`.
+pub fn compute(origin: IVec2, range: i32, map: &mut impl VisiblityMap) {
+    map.set_visible_intensity(origin, 1.0);
+    for octant in 0..8 {
+        compute_octant(
+            octant,
+            origin,
+            range,
+            1,
+            Slope { y: 1, x: 1 },
+            Slope { y: 0, x: 1 },
+            map,
+        );
+    }
+}
+
+/// Computes a directional field of view from `origin` out to `range`,
+/// restricted to a cone facing `facing` with half-angle `half_angle_radians`.
+///
+/// Runs the same shadowcast as [`compute`], but only marks points inside
+/// the facing cone as visible. The origin cell is always visible, and
+/// points within 1 tile of the origin skip the angle test so the near
+/// field doesn't get a jagged edge.
+pub fn compute_cone(
+    origin: IVec2,
+    facing: Vec2,
+    half_angle_radians: f32,
+    range: i32,
+    map: &mut impl VisiblityMap,
+) {
+    let facing = facing.normalize();
+    let min_dot = half_angle_radians.cos();
+
+    let mut cone = ConeMap {
+        map,
+        origin,
+        facing,
+        min_dot,
+    };
+
+    cone.map.set_visible_intensity(origin, 1.0);
+    for octant in 0..8 {
+        compute_octant(
+            octant,
+            origin,
+            range,
+            1,
+            Slope { y: 1, x: 1 },
+            Slope { y: 0, x: 1 },
+            &mut cone,
+        );
+    }
+}
+
+/// Wraps a [`VisiblityMap`] so shadowcasting can run unmodified while
+/// `set_visible` gates each cell through the facing cone test.
+struct ConeMap<'a, M: VisiblityMap> {
+    map: &'a mut M,
+    origin: IVec2,
+    facing: Vec2,
+    min_dot: f32,
+}
+
+impl<'a, M: VisiblityMap> VisiblityMap for ConeMap<'a, M> {
+    fn is_opaque(&self, p: IVec2) -> bool {
+        self.map.is_opaque(p)
+    }
+
+    fn is_in_bounds(&self, p: IVec2) -> bool {
+        self.map.is_in_bounds(p)
+    }
+
+    fn set_visible(&mut self, p: IVec2) {
+        if self.in_cone(p) {
+            self.map.set_visible(p);
+        }
+    }
+
+    fn set_visible_intensity(&mut self, p: IVec2, intensity: f32) {
+        if self.in_cone(p) {
+            self.map.set_visible_intensity(p, intensity);
+        }
+    }
+
+    fn dist(&self, a: IVec2, b: IVec2) -> f32 {
+        self.map.dist(a, b)
+    }
+}
+
+impl<'a, M: VisiblityMap> ConeMap<'a, M> {
+    fn in_cone(&self, p: IVec2) -> bool {
+        let v = p - self.origin;
+        v.x.abs().max(v.y.abs()) <= 1 || v.as_vec2().normalize().dot(self.facing) >= self.min_dot
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn compute_octant(
+    octant: usize,
+    origin: IVec2,
+    range: i32,
+    mut x: i32,
+    mut top: Slope,
+    mut bottom: Slope,
+    map: &mut impl VisiblityMap,
+) {
+    while x <= range {
+        let top_y = if top.x == 1 {
+            x
+        } else {
+            let mut top_y = ((x * 2 - 1) * top.y + top.x) / (top.x * 2);
+            if blocks_light(x, top_y, octant, origin, map) {
+                if top.greater_or_equal(top_y * 2 + 1, x * 2)
+                    && !blocks_light(x, top_y + 1, octant, origin, map)
+                {
+                    top_y += 1;
+                }
+            } else {
+                let mut ax = x * 2;
+                if blocks_light(x + 1, top_y + 1, octant, origin, map) {
+                    ax += 1;
+                }
+                if top.greater(top_y * 2 + 1, ax) {
+                    top_y += 1;
+                }
+            }
+            top_y
+        };
+
+        let bottom_y = if bottom.y == 0 {
+            0
+        } else {
+            let mut bottom_y = ((x * 2 - 1) * bottom.y + bottom.x) / (bottom.x * 2);
+            if bottom.greater_or_equal(bottom_y * 2 + 1, x * 2)
+                && blocks_light(x, bottom_y, octant, origin, map)
+                && !blocks_light(x, bottom_y + 1, octant, origin, map)
+            {
+                bottom_y += 1;
+            }
+            bottom_y
+        };
+
+        let mut was_opaque = -1;
+        let mut y = top_y;
+        while y >= bottom_y {
+            let p = transform(x, y, octant, origin);
+
+            let d = map.dist(origin, p);
+            if range < 0 || d <= range as f32 {
+                let is_opaque = blocks_light(x, y, octant, origin, map);
+                let is_visible = is_opaque
+                    || ((y != top_y || top.greater(y * 4 - 1, x * 4 + 1))
+                        && (y != bottom_y || bottom.less(y * 4 + 1, x * 4 - 1)));
+                if is_visible {
+                    let intensity = if range > 0 {
+                        (1.0 - d / range as f32).clamp(0.0, 1.0)
+                    } else {
+                        1.0
+                    };
+                    map.set_visible_intensity(p, intensity);
+                }
+
+                if x != range {
+                    if is_opaque {
+                        if was_opaque == 0 {
+                            let mut nx = x * 2;
+                            let ny = y * 2 + 1;
+                            if blocks_light(x, y + 1, octant, origin, map) {
+                                nx -= 1;
+                            }
+                            if top.greater(ny, nx) {
+                                if y == bottom_y {
+                                    bottom = Slope { y: ny, x: nx };
+                                    break;
+                                } else {
+                                    compute_octant(octant, origin, range, x + 1, top, Slope { y: ny, x: nx }, map);
+                                }
+                            } else if y == bottom_y {
+                                return;
+                            }
+                        }
+                        was_opaque = 1;
+                    } else {
+                        if was_opaque > 0 {
+                            let mut nx = x * 2;
+                            let ny = y * 2 + 1;
+                            if blocks_light(x + 1, y + 1, octant, origin, map) {
+                                nx += 1;
+                            }
+                            if bottom.greater_or_equal(ny, nx) {
+                                return;
+                            }
+                            top = Slope { y: ny, x: nx };
+                        }
+                        was_opaque = 0;
+                    }
+                }
+            }
+
+            y -= 1;
+        }
+
+        if was_opaque != 0 {
+            break;
+        }
+        x += 1;
+    }
+}
+
+fn transform(x: i32, y: i32, octant: usize, origin: IVec2) -> IVec2 {
+    IVec2::new(
+        origin.x + x * MULT[0][octant] + y * MULT[1][octant],
+        origin.y + x * MULT[2][octant] + y * MULT[3][octant],
+    )
+}
+
+fn blocks_light(x: i32, y: i32, octant: usize, origin: IVec2, map: &impl VisiblityMap) -> bool {
+    let p = transform(x, y, octant, origin);
+    !map.is_in_bounds(p) || map.is_opaque(p)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GridVisibilityMap;
+
+    #[test]
+    fn compute_lights_open_room_out_to_range() {
+        let mut map = GridVisibilityMap::new(11, 11);
+        let origin = IVec2::new(5, 5);
+
+        compute(origin, 5, &mut map);
+
+        assert!(map.is_visible(origin));
+        assert!(map.is_visible(origin + IVec2::new(5, 0)));
+        assert!(!map.is_visible(IVec2::new(0, 0)));
+    }
+
+    #[test]
+    fn compute_stops_at_walls() {
+        let mut map = GridVisibilityMap::new(11, 11);
+        let origin = IVec2::new(5, 5);
+        map.set_opaque(origin + IVec2::new(1, 0), true);
+
+        compute(origin, 5, &mut map);
+
+        assert!(!map.is_visible(origin + IVec2::new(2, 0)));
+    }
+
+    #[test]
+    fn compute_cone_only_lights_facing_direction() {
+        let mut map = GridVisibilityMap::new(11, 11);
+        let origin = IVec2::new(5, 5);
+
+        compute_cone(origin, Vec2::new(1.0, 0.0), 0.4, 5, &mut map);
+
+        assert!(map.is_visible(origin));
+        assert!(map.is_visible(origin + IVec2::new(5, 0)));
+        assert!(!map.is_visible(origin + IVec2::new(-5, 0)));
+    }
+
+    #[test]
+    fn compute_cone_always_lights_the_near_field() {
+        let mut map = GridVisibilityMap::new(11, 11);
+        let origin = IVec2::new(5, 5);
+
+        compute_cone(origin, Vec2::new(1.0, 0.0), 0.01, 5, &mut map);
+
+        assert!(map.is_visible(origin + IVec2::new(0, 1)));
+        assert!(map.is_visible(origin + IVec2::new(-1, 1)));
+    }
+}
@@ -0,0 +1,223 @@
+//! A ready-made [`VisiblityMap`] so simple grids don't need to hand-roll one.
+
+use glam::IVec2;
+
+use crate::VisiblityMap;
+
+/// A rectangular grid of opaque/visible cells with a built-in
+/// [`VisiblityMap`] implementation.
+///
+/// Most consumers don't need a custom map type - `GridVisibilityMap` covers
+/// the common case of "a flat grid of walls" so you can call
+/// [`fov::compute`](crate::fov::compute) without reimplementing the same
+/// `Vec<bool>` bookkeeping every time.
+pub struct GridVisibilityMap {
+    width: i32,
+    height: i32,
+    opaque: Vec<bool>,
+    visible: Vec<bool>,
+    intensity: Vec<f32>,
+}
+
+impl GridVisibilityMap {
+    /// Creates a new grid of the given size, with every cell transparent
+    /// and not visible.
+    pub fn new(width: i32, height: i32) -> Self {
+        let len = (width * height) as usize;
+        GridVisibilityMap {
+            width,
+            height,
+            opaque: vec![false; len],
+            visible: vec![false; len],
+            intensity: vec![0.0; len],
+        }
+    }
+
+    /// The grid's width, in cells.
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    /// The grid's height, in cells.
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+
+    /// Flips whether the given point is opaque. No-ops if out of bounds.
+    pub fn toggle_opaque(&mut self, p: IVec2) {
+        if !self.is_in_bounds(p) {
+            return;
+        }
+        let i = self.to_index(p);
+        self.opaque[i] = !self.opaque[i];
+    }
+
+    /// Sets whether the given point is opaque. No-ops if out of bounds.
+    pub fn set_opaque(&mut self, p: IVec2, opaque: bool) {
+        if !self.is_in_bounds(p) {
+            return;
+        }
+        let i = self.to_index(p);
+        self.opaque[i] = opaque;
+    }
+
+    /// Clears visibility for every cell, ready for the next
+    /// [`fov::compute`](crate::fov::compute) call.
+    pub fn clear_visible(&mut self) {
+        self.visible.iter_mut().for_each(|v| *v = false);
+        self.intensity.iter_mut().for_each(|v| *v = 0.0);
+    }
+
+    /// Returns true if the given point is currently visible.
+    ///
+    /// This tracks whether the FOV pass touched the cell at all, separate
+    /// from [`intensity`](Self::intensity) - a cell at the very edge of the
+    /// view range can have an intensity of `0.0` and still be visible.
+    pub fn is_visible(&self, p: IVec2) -> bool {
+        self.is_in_bounds(p) && self.visible[self.to_index(p)]
+    }
+
+    /// Returns the visibility intensity of the given point, or `0.0` if
+    /// it's out of bounds or not visible.
+    pub fn intensity(&self, p: IVec2) -> f32 {
+        if !self.is_in_bounds(p) {
+            return 0.0;
+        }
+        self.intensity[self.to_index(p)]
+    }
+
+    /// Iterates every currently visible point on the grid.
+    pub fn iter_visible(&self) -> impl Iterator<Item = IVec2> + '_ {
+        self.visible.iter().enumerate().filter_map(move |(i, v)| {
+            if *v {
+                Some(self.index_to_point(i))
+            } else {
+                None
+            }
+        })
+    }
+
+    fn to_index(&self, p: IVec2) -> usize {
+        (p.y * self.width + p.x) as usize
+    }
+
+    fn index_to_point(&self, i: usize) -> IVec2 {
+        let i = i as i32;
+        IVec2::new(i % self.width, i / self.width)
+    }
+}
+
+impl VisiblityMap for GridVisibilityMap {
+    fn is_opaque(&self, p: IVec2) -> bool {
+        if !self.is_in_bounds(p) {
+            return true;
+        }
+        self.opaque[self.to_index(p)]
+    }
+
+    fn is_in_bounds(&self, p: IVec2) -> bool {
+        p.x >= 0 && p.x < self.width && p.y >= 0 && p.y < self.height
+    }
+
+    fn set_visible(&mut self, p: IVec2) {
+        self.set_visible_intensity(p, 1.0);
+    }
+
+    fn set_visible_intensity(&mut self, p: IVec2, intensity: f32) {
+        if !self.is_in_bounds(p) {
+            return;
+        }
+        let i = self.to_index(p);
+        self.visible[i] = true;
+        // Combine rather than overwrite so multiple FOV sources (e.g.
+        // several AdamFovPlugin viewers) OR together instead of the last
+        // writer stomping an earlier, brighter one.
+        self.intensity[i] = self.intensity[i].max(intensity);
+    }
+
+    fn dist(&self, a: IVec2, b: IVec2) -> f32 {
+        a.as_vec2().distance(b.as_vec2())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fov;
+
+    #[test]
+    fn toggle_opaque_flips_the_cell() {
+        let mut map = GridVisibilityMap::new(4, 4);
+        let p = IVec2::new(1, 1);
+
+        assert!(!map.is_opaque(p));
+        map.toggle_opaque(p);
+        assert!(map.is_opaque(p));
+        map.toggle_opaque(p);
+        assert!(!map.is_opaque(p));
+    }
+
+    #[test]
+    fn toggle_and_set_opaque_no_op_out_of_bounds() {
+        let mut map = GridVisibilityMap::new(4, 4);
+        let p = IVec2::new(50, 50);
+
+        map.toggle_opaque(p);
+        map.set_opaque(p, true);
+    }
+
+    #[test]
+    fn out_of_bounds_points_are_opaque_and_not_visible() {
+        let map = GridVisibilityMap::new(4, 4);
+        assert!(map.is_opaque(IVec2::new(-1, 0)));
+        assert!(!map.is_visible(IVec2::new(-1, 0)));
+    }
+
+    #[test]
+    fn edge_of_range_stays_visible_at_zero_intensity() {
+        let mut map = GridVisibilityMap::new(11, 11);
+        let origin = IVec2::new(5, 5);
+
+        fov::compute(origin, 5, &mut map);
+        let edge = origin + IVec2::new(5, 0);
+
+        assert_eq!(map.intensity(edge), 0.0);
+        assert!(map.is_visible(edge));
+    }
+
+    #[test]
+    fn set_visible_intensity_combines_with_max_not_last_writer() {
+        let mut map = GridVisibilityMap::new(4, 4);
+        let p = IVec2::new(2, 2);
+
+        map.set_visible_intensity(p, 1.0);
+        map.set_visible_intensity(p, 0.2);
+
+        assert_eq!(map.intensity(p), 1.0);
+        assert!(map.is_visible(p));
+    }
+
+    #[test]
+    fn clear_visible_resets_visibility_and_intensity() {
+        let mut map = GridVisibilityMap::new(4, 4);
+        let p = IVec2::new(2, 2);
+
+        map.set_visible(p);
+        map.clear_visible();
+
+        assert!(!map.is_visible(p));
+        assert_eq!(map.intensity(p), 0.0);
+    }
+
+    #[test]
+    fn iter_visible_yields_every_visible_point_once() {
+        let mut map = GridVisibilityMap::new(4, 4);
+        map.set_visible(IVec2::new(0, 0));
+        map.set_visible(IVec2::new(3, 3));
+
+        let mut visible: Vec<_> = map.iter_visible().collect();
+        visible.sort_by_key(|p| (p.x, p.y));
+
+        assert_eq!(visible, vec![IVec2::new(0, 0), IVec2::new(3, 3)]);
+    }
+}
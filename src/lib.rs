@@ -0,0 +1,56 @@
+//! A small, dependency-light field-of-view library for grid-based games.
+//!
+//! The crate doesn't require a particular map type - implement
+//! [`VisiblityMap`] for whatever grid you already have, or use the
+//! ready-made [`GridVisibilityMap`], and call [`fov::compute`] (or
+//! [`fov::compute_cone`]) to mark the cells it can see.
+
+use glam::IVec2;
+
+pub mod fov;
+mod grid;
+#[cfg(feature = "bevy")]
+mod cursor;
+#[cfg(feature = "bevy")]
+mod plugin;
+
+pub use grid::GridVisibilityMap;
+#[cfg(feature = "bevy")]
+pub use cursor::{CursorMapPos, CursorMapPosPlugin, MapSize};
+#[cfg(feature = "bevy")]
+pub use plugin::{AdamFovPlugin, FovMap, FovViewer};
+
+/// A grid that [`fov::compute`] can read opacity from and write visibility to.
+///
+/// Implement this on your own map type - the trait only asks for the bits
+/// shadowcasting actually needs, so it stays agnostic to how you store or
+/// render your grid.
+pub trait VisiblityMap {
+    /// Returns true if the given point blocks line of sight.
+    ///
+    /// Points outside the map should be treated as opaque so the
+    /// shadowcast doesn't bleed past the map's edges.
+    fn is_opaque(&self, p: IVec2) -> bool;
+
+    /// Returns true if the given point is inside the map's bounds.
+    fn is_in_bounds(&self, p: IVec2) -> bool;
+
+    /// Marks the given point as visible.
+    fn set_visible(&mut self, p: IVec2);
+
+    /// Marks the given point as visible with a graded `intensity` in
+    /// `0.0..=1.0`, letting consumers fade lighting/glyphs toward the edge
+    /// of a view range instead of showing a flat lit disc.
+    ///
+    /// Defaults to ignoring `intensity` and forwarding to [`set_visible`],
+    /// so existing implementers keep compiling unchanged.
+    ///
+    /// [`set_visible`]: VisiblityMap::set_visible
+    fn set_visible_intensity(&mut self, p: IVec2, intensity: f32) {
+        let _ = intensity;
+        self.set_visible(p);
+    }
+
+    /// Returns the distance between two points on the map.
+    fn dist(&self, a: IVec2, b: IVec2) -> f32;
+}
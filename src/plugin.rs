@@ -0,0 +1,96 @@
+//! A Bevy plugin that keeps FOV up to date automatically, mirroring this
+//! ecosystem's `CameraPlugin`/`ActorPlugin`/`MousePosPlugin` style: insert
+//! [`FovMap`], spawn entities with [`FovViewer`], add [`AdamFovPlugin`], and
+//! read the map - no manual `clear_visible`/`fov::compute` wiring per game.
+
+use std::ops::{Deref, DerefMut};
+
+use bevy::prelude::*;
+
+use crate::{fov, GridVisibilityMap, VisiblityMap};
+
+/// Marks an entity as an FOV source, sighted out to `range` from its
+/// [`Transform`].
+#[derive(Component, Clone, Copy)]
+pub struct FovViewer {
+    pub range: i32,
+}
+
+/// The shared visibility grid [`AdamFovPlugin`] recomputes into.
+///
+/// A thin [`VisiblityMap`] wrapper over [`GridVisibilityMap`] so it can be
+/// inserted as a resource and read the same way you'd read any map.
+#[derive(Resource)]
+pub struct FovMap(GridVisibilityMap);
+
+impl FovMap {
+    /// Creates a new map resource of the given size, with every cell
+    /// transparent and not visible.
+    pub fn new(width: i32, height: i32) -> Self {
+        FovMap(GridVisibilityMap::new(width, height))
+    }
+}
+
+impl Deref for FovMap {
+    type Target = GridVisibilityMap;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for FovMap {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl VisiblityMap for FovMap {
+    fn is_opaque(&self, p: glam::IVec2) -> bool {
+        self.0.is_opaque(p)
+    }
+
+    fn is_in_bounds(&self, p: glam::IVec2) -> bool {
+        self.0.is_in_bounds(p)
+    }
+
+    fn set_visible(&mut self, p: glam::IVec2) {
+        self.0.set_visible(p);
+    }
+
+    fn set_visible_intensity(&mut self, p: glam::IVec2, intensity: f32) {
+        self.0.set_visible_intensity(p, intensity);
+    }
+
+    fn dist(&self, a: glam::IVec2, b: glam::IVec2) -> f32 {
+        self.0.dist(a, b)
+    }
+}
+
+/// Recomputes [`FovMap`] from every [`FovViewer`] whenever a viewer's
+/// `Transform` or `range` changes, OR-ing every viewer's visibility into
+/// the same map so multiple simultaneous viewers just work.
+pub struct AdamFovPlugin;
+
+impl Plugin for AdamFovPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PostUpdate, recompute_fov);
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn recompute_fov(
+    mut map: ResMut<FovMap>,
+    changed: Query<(), Or<(Changed<Transform>, Changed<FovViewer>)>>,
+    viewers: Query<(&FovViewer, &Transform)>,
+) {
+    if changed.is_empty() {
+        return;
+    }
+
+    map.clear_visible();
+    for (viewer, transform) in &viewers {
+        let origin = transform.translation.truncate().floor().as_ivec2();
+        fov::compute(origin, viewer.range, &mut *map);
+    }
+}